@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::errors::AppError;
+use crate::services::file_service::{FileInfo, FileService};
+use crate::services::vault_service::VaultService;
+
+fn vault_root(vault_service: &VaultService) -> Result<PathBuf, AppError> {
+    let vault = vault_service
+        .get_current_vault()?
+        .ok_or_else(|| AppError::Unknown("No vault is open".to_string()))?;
+    Ok(PathBuf::from(vault.path))
+}
+
+#[tauri::command]
+pub async fn read_note(
+    vault_service: State<'_, VaultService>,
+    file_service: State<'_, FileService>,
+    path: String,
+) -> Result<String, AppError> {
+    let root = vault_root(&vault_service)?;
+    let key = vault_service.vault_key()?;
+    file_service.read_note(&root, key.as_ref(), &path).await
+}
+
+#[tauri::command]
+pub async fn write_note(
+    vault_service: State<'_, VaultService>,
+    file_service: State<'_, FileService>,
+    path: String,
+    content: String,
+) -> Result<(), AppError> {
+    let root = vault_root(&vault_service)?;
+    let key = vault_service.vault_key()?;
+    file_service
+        .write_note(&root, key.as_ref(), &path, &content)
+        .await
+}
+
+#[tauri::command]
+pub async fn create_note(
+    vault_service: State<'_, VaultService>,
+    file_service: State<'_, FileService>,
+    path: String,
+) -> Result<(), AppError> {
+    let root = vault_root(&vault_service)?;
+    let key = vault_service.vault_key()?;
+    file_service.create_note(&root, key.as_ref(), &path).await
+}
+
+#[tauri::command]
+pub async fn delete_note(
+    vault_service: State<'_, VaultService>,
+    file_service: State<'_, FileService>,
+    path: String,
+) -> Result<(), AppError> {
+    let root = vault_root(&vault_service)?;
+    file_service.delete_note(&root, &path).await
+}
+
+#[tauri::command]
+pub async fn list_notes(
+    vault_service: State<'_, VaultService>,
+    file_service: State<'_, FileService>,
+) -> Result<Vec<String>, AppError> {
+    let root = vault_root(&vault_service)?;
+    file_service.list_notes(&root).await
+}
+
+#[tauri::command]
+pub async fn file_exists(
+    vault_service: State<'_, VaultService>,
+    file_service: State<'_, FileService>,
+    path: String,
+) -> Result<bool, AppError> {
+    let root = vault_root(&vault_service)?;
+    file_service.file_exists(&root, &path).await
+}
+
+#[tauri::command]
+pub async fn get_file_info(
+    vault_service: State<'_, VaultService>,
+    file_service: State<'_, FileService>,
+    path: String,
+) -> Result<FileInfo, AppError> {
+    let root = vault_root(&vault_service)?;
+    file_service.get_file_info(&root, &path).await
+}
@@ -46,6 +46,24 @@ pub fn close_vault(vault_service: State<'_, VaultService>) -> Result<(), AppErro
     vault_service.close_vault()
 }
 
+#[tauri::command]
+pub fn unlock_vault(
+    vault_service: State<'_, VaultService>,
+    password: String,
+) -> Result<(), AppError> {
+    vault_service.unlock_vault(password)
+}
+
+#[tauri::command]
+pub fn lock_vault(vault_service: State<'_, VaultService>) -> Result<(), AppError> {
+    vault_service.lock_vault()
+}
+
+#[tauri::command]
+pub async fn refresh_vault(vault_service: State<'_, VaultService>) -> Result<Vault, AppError> {
+    vault_service.refresh_vault().await
+}
+
 #[tauri::command]
 pub async fn is_vault(
     vault_service: State<'_, VaultService>,
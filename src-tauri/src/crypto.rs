@@ -0,0 +1,177 @@
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::{Argon2, Params};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use crate::errors::AppError;
+use crate::models::vault::{EncryptionConfig, KdfParams};
+
+const DEFAULT_ITERATIONS: u32 = 3;
+const DEFAULT_MEMORY_KIB: u32 = 19_456; // Argon2id OWASP baseline (~19 MiB)
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// A symmetric key derived from a vault's password. Held only in memory for the
+/// lifetime of an unlocked session - it is never serialized or written to disk.
+#[derive(Clone)]
+pub struct VaultKey(Vec<u8>);
+
+impl VaultKey {
+    /// Derive a fresh key from `password` and a newly generated salt, returning the
+    /// key alongside the `EncryptionConfig` to persist in `VaultConfig`.
+    pub fn generate(password: &str) -> Result<(Self, EncryptionConfig), AppError> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let key = Self::derive(password, &salt, DEFAULT_ITERATIONS, DEFAULT_MEMORY_KIB)?;
+        let key_mac = key.compute_mac();
+
+        let config = EncryptionConfig {
+            kdf: KdfParams {
+                salt: STANDARD.encode(salt),
+                iterations: DEFAULT_ITERATIONS,
+                memory_kib: DEFAULT_MEMORY_KIB,
+            },
+            key_mac,
+        };
+
+        Ok((key, config))
+    }
+
+    /// Re-derive the key from `password` using the stored KDF params, rejecting it
+    /// if it doesn't match the stored MAC.
+    pub fn unlock(password: &str, config: &EncryptionConfig) -> Result<Self, AppError> {
+        let salt = STANDARD
+            .decode(&config.kdf.salt)
+            .map_err(|_| AppError::Unknown("Vault encryption config is corrupt".to_string()))?;
+
+        let key = Self::derive(password, &salt, config.kdf.iterations, config.kdf.memory_kib)?;
+
+        // Constant-time comparison - this gates password acceptance, so a
+        // byte-by-byte `!=` would leak timing information about the correct MAC.
+        let macs_match: bool = key
+            .compute_mac()
+            .as_bytes()
+            .ct_eq(config.key_mac.as_bytes())
+            .into();
+        if !macs_match {
+            return Err(AppError::Unknown("Incorrect vault password".to_string()));
+        }
+
+        Ok(key)
+    }
+
+    fn derive(
+        password: &str,
+        salt: &[u8],
+        iterations: u32,
+        memory_kib: u32,
+    ) -> Result<Self, AppError> {
+        let params = Params::new(memory_kib, iterations, 1, Some(KEY_LEN))
+            .map_err(|e| AppError::Unknown(format!("Invalid KDF params: {}", e)))?;
+        let argon2 = Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+
+        let mut key = vec![0u8; KEY_LEN];
+        argon2
+            .hash_password_into(password.as_bytes(), salt, &mut key)
+            .map_err(|e| AppError::Unknown(format!("Key derivation failed: {}", e)))?;
+
+        Ok(Self(key))
+    }
+
+    /// MAC of the derived key itself, not a password hash - lets us verify a password
+    /// without ever decrypting real content.
+    fn compute_mac(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.0);
+        hasher.update(b"onix-vault-key-mac");
+        STANDARD.encode(hasher.finalize())
+    }
+
+    /// Encrypt `plaintext`, returning a base64 string of `nonce || ciphertext` (the
+    /// ciphertext includes the AEAD tag), suitable for storing directly in JSON.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<String, AppError> {
+        let cipher = Aes256Gcm::new_from_slice(&self.0)
+            .map_err(|e| AppError::Unknown(format!("Invalid vault key: {}", e)))?;
+
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce_bytes);
+        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        let ciphertext = cipher
+            .encrypt(nonce, plaintext)
+            .map_err(|e| AppError::Unknown(format!("Encryption failed: {}", e)))?;
+
+        let mut out = nonce_bytes.to_vec();
+        out.extend_from_slice(&ciphertext);
+        Ok(STANDARD.encode(out))
+    }
+
+    /// Decrypt a value produced by [`VaultKey::encrypt`].
+    pub fn decrypt(&self, encoded: &str) -> Result<Vec<u8>, AppError> {
+        let cipher = Aes256Gcm::new_from_slice(&self.0)
+            .map_err(|e| AppError::Unknown(format!("Invalid vault key: {}", e)))?;
+
+        let raw = STANDARD
+            .decode(encoded)
+            .map_err(|_| AppError::Unknown("Corrupt encrypted payload".to_string()))?;
+
+        if raw.len() < NONCE_LEN {
+            return Err(AppError::Unknown("Corrupt encrypted payload".to_string()));
+        }
+
+        let (nonce_bytes, ciphertext) = raw.split_at(NONCE_LEN);
+        let nonce = Nonce::from_slice(nonce_bytes);
+
+        cipher
+            .decrypt(nonce, ciphertext)
+            .map_err(|_| AppError::Unknown("Decryption failed - wrong password?".to_string()))
+    }
+}
+
+impl std::fmt::Debug for VaultKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultKey").field("key", &"<redacted>").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlock_accepts_the_correct_password() {
+        let (_, config) = VaultKey::generate("correct horse").unwrap();
+        assert!(VaultKey::unlock("correct horse", &config).is_ok());
+    }
+
+    #[test]
+    fn unlock_rejects_the_wrong_password() {
+        let (_, config) = VaultKey::generate("correct horse").unwrap();
+        assert!(VaultKey::unlock("wrong password", &config).is_err());
+    }
+
+    #[test]
+    fn encrypt_decrypt_round_trips() {
+        let (key, _) = VaultKey::generate("hunter2").unwrap();
+        let plaintext = b"some vault metadata";
+
+        let ciphertext = key.encrypt(plaintext).unwrap();
+        assert_ne!(ciphertext.as_bytes(), plaintext);
+        assert_eq!(key.decrypt(&ciphertext).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn decrypt_fails_with_a_different_key() {
+        let (key_a, _) = VaultKey::generate("password-a").unwrap();
+        let (key_b, _) = VaultKey::generate("password-b").unwrap();
+
+        let ciphertext = key_a.encrypt(b"secret").unwrap();
+        assert!(key_b.decrypt(&ciphertext).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 mod commands;
+mod crypto;
 mod errors;
+mod migrations;
 mod models;
 mod services;
 
@@ -30,7 +32,10 @@ pub fn run() {
             vault::close_vault,
             vault::is_vault,
             vault::select_folder,
-            vault::test_dialog_functionality
+            vault::test_dialog_functionality,
+            vault::unlock_vault,
+            vault::lock_vault,
+            vault::refresh_vault
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
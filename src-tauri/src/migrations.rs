@@ -0,0 +1,124 @@
+use serde_json::Value;
+
+/// Current on-disk schema version for `VaultConfig`. Bump this and add an entry to
+/// `MIGRATIONS` whenever the persisted JSON shape changes.
+pub const CURRENT_CONFIG_VERSION: &str = "1.0.0";
+
+/// Version assumed for a config with no `version` field, i.e. one written before
+/// this field existed.
+const LEGACY_VERSION: &str = "0.0.0";
+
+type MigrationFn = fn(Value) -> Value;
+
+/// Ordered chain of migrations, keyed by the version they migrate *from*. Applied
+/// repeatedly until the config reports `CURRENT_CONFIG_VERSION`.
+const MIGRATIONS: &[(&str, MigrationFn)] = &[(LEGACY_VERSION, migrate_legacy_to_v1)];
+
+/// Upper bound on migration steps, well above `MIGRATIONS.len()`. Guards against a
+/// migration that doesn't advance the version looping forever.
+const MAX_MIGRATION_STEPS: usize = 16;
+
+/// Migrate a vault's raw config JSON forward to `CURRENT_CONFIG_VERSION`, returning
+/// the transformed JSON and whether any migration actually ran. A migration that
+/// runs but leaves the version unchanged (e.g. malformed input it couldn't touch)
+/// is treated the same as "no migration found" - the caller's `from_value` will
+/// then fail on the still-outdated shape and fall back to crash recovery.
+pub fn migrate_to_current(mut config: Value) -> (Value, bool) {
+    let mut version = config_version(&config);
+    let mut migrated = false;
+
+    for _ in 0..MAX_MIGRATION_STEPS {
+        if version == CURRENT_CONFIG_VERSION {
+            break;
+        }
+
+        let Some((_, migration)) = MIGRATIONS.iter().find(|(from, _)| *from == version) else {
+            break;
+        };
+
+        let next_config = migration(config.clone());
+        let next_version = config_version(&next_config);
+        if next_version == version {
+            break;
+        }
+
+        config = next_config;
+        migrated = true;
+        version = next_version;
+    }
+
+    (config, migrated)
+}
+
+/// Read a config's version, treating a missing `version` field as the pre-versioning
+/// legacy schema.
+fn config_version(config: &Value) -> String {
+    config
+        .get("config")
+        .and_then(|cfg| cfg.get("version"))
+        .and_then(|v| v.as_str())
+        .unwrap_or(LEGACY_VERSION)
+        .to_string()
+}
+
+/// 0.0.0 -> 1.0.0: introduce `version`, `created_at`, and `modified_at` on the config
+/// itself, backfilling timestamps from the parent vault's `created_at`.
+fn migrate_legacy_to_v1(mut config: Value) -> Value {
+    let fallback_created_at = config
+        .get("created_at")
+        .cloned()
+        .unwrap_or_else(|| Value::String("0".to_string()));
+
+    if let Some(cfg) = config.get_mut("config").and_then(|c| c.as_object_mut()) {
+        cfg.entry("version")
+            .or_insert_with(|| Value::String(CURRENT_CONFIG_VERSION.to_string()));
+        cfg.entry("created_at")
+            .or_insert_with(|| fallback_created_at.clone());
+        cfg.entry("modified_at").or_insert(fallback_created_at);
+    }
+
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrates_legacy_config_and_backfills_timestamps() {
+        let legacy = json!({
+            "created_at": "100",
+            "config": { "name": "My Vault" }
+        });
+
+        let (migrated, did_migrate) = migrate_to_current(legacy);
+
+        assert!(did_migrate);
+        assert_eq!(migrated["config"]["version"], CURRENT_CONFIG_VERSION);
+        assert_eq!(migrated["config"]["created_at"], "100");
+        assert_eq!(migrated["config"]["modified_at"], "100");
+    }
+
+    #[test]
+    fn current_config_is_left_untouched() {
+        let current = json!({ "config": { "version": CURRENT_CONFIG_VERSION } });
+
+        let (migrated, did_migrate) = migrate_to_current(current.clone());
+
+        assert!(!did_migrate);
+        assert_eq!(migrated, current);
+    }
+
+    #[test]
+    fn stalled_migration_terminates_instead_of_looping_forever() {
+        // No "config" object for `migrate_legacy_to_v1` to backfill into, so the
+        // version can never advance past the legacy default.
+        let unmigratable = json!({});
+
+        let (migrated, did_migrate) = migrate_to_current(unmigratable.clone());
+
+        assert!(!did_migrate);
+        assert_eq!(migrated, unmigratable);
+    }
+}
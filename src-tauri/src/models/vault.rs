@@ -16,10 +16,42 @@ pub struct Vault {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VaultConfig {
+    /// Schema version of this config, used by the migration subsystem to upgrade
+    /// older `vault.json` files on open rather than failing to parse them.
+    #[serde(default = "VaultConfig::default_version")]
+    pub version: String,
     pub name: String,
     pub description: Option<String>,
     pub settings: VaultSettings,
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Ciphertext of `metadata` for an encrypted vault (base64 `nonce || ciphertext`).
+    /// `metadata` itself is always empty on disk in that case; it's populated in
+    /// memory only after `unlock_vault` succeeds.
+    #[serde(default)]
+    pub encrypted_metadata: Option<String>,
+    /// Present when the vault is encrypted at rest. Absent for plain vaults.
+    #[serde(default)]
+    pub encryption: Option<EncryptionConfig>,
+    pub created_at: String,
+    pub modified_at: String,
+}
+
+/// KDF descriptor and key MAC for an encrypted vault. Never stores the password
+/// itself, only what's needed to re-derive and verify the key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    pub kdf: KdfParams,
+    /// Base64-encoded MAC of the derived key, checked on unlock to reject a wrong
+    /// password up front.
+    pub key_mac: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Base64-encoded random salt used to derive the vault key from the password.
+    pub salt: String,
+    pub iterations: u32,
+    pub memory_kib: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +60,38 @@ pub struct VaultSettings {
     pub default_note_location: String,
     pub file_extensions: Vec<String>,
     pub exclude_patterns: Vec<String>,
+    /// Worker threads for parallel vault scans. Defaults to the available cores.
+    #[serde(default = "VaultSettings::default_scan_workers")]
+    pub scan_workers: usize,
+    /// How much of the tree a scan indexes.
+    #[serde(default)]
+    pub tree_mode: ScanMode,
+    /// Additionally exclude whatever a `.gitignore` in the vault root matches.
+    #[serde(default)]
+    pub respect_gitignore: bool,
+}
+
+/// How much of the vault tree a scan walks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScanMode {
+    /// Only the top-level `default_note_location` directory, non-recursive.
+    Flat,
+    /// The entire tree, recursively.
+    DepthFirst,
+}
+
+impl Default for ScanMode {
+    fn default() -> Self {
+        ScanMode::DepthFirst
+    }
+}
+
+/// A single file's last-known size and modification time, for incremental scans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileIndexEntry {
+    pub size: u64,
+    /// Unix timestamp (seconds) of the file's mtime at last scan.
+    pub modified: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +110,10 @@ pub struct CreateVaultRequest {
     pub path: String,
     pub name: String,
     pub description: Option<String>,
+    /// Opt-in: when set, the vault's metadata and note bodies are encrypted with a
+    /// key derived from this password. The password itself is never stored.
+    #[serde(default)]
+    pub password: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +124,14 @@ pub struct RecentVault {
     pub last_opened: String,
 }
 
+impl VaultSettings {
+    fn default_scan_workers() -> usize {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    }
+}
+
 impl Default for VaultSettings {
     fn default() -> Self {
         Self {
@@ -68,17 +144,33 @@ impl Default for VaultSettings {
                 "node_modules".to_string(),
                 ".DS_Store".to_string(),
             ],
+            scan_workers: Self::default_scan_workers(),
+            tree_mode: ScanMode::default(),
+            respect_gitignore: false,
         }
     }
 }
 
+impl VaultConfig {
+    fn default_version() -> String {
+        crate::migrations::CURRENT_CONFIG_VERSION.to_string()
+    }
+}
+
 impl Default for VaultConfig {
     fn default() -> Self {
+        let now = chrono::Utc::now().timestamp().to_string();
+
         Self {
+            version: Self::default_version(),
             name: "My Vault".to_string(),
             description: None,
             settings: VaultSettings::default(),
             metadata: HashMap::new(),
+            encrypted_metadata: None,
+            encryption: None,
+            created_at: now.clone(),
+            modified_at: now,
         }
     }
 }
@@ -106,6 +198,11 @@ impl Vault {
         PathBuf::from(&self.path).join(".onix").join("vault.json")
     }
 
+    /// Staging path used for write-to-temp-then-rename writes of `vault.json`.
+    pub fn temp_config_path(&self) -> PathBuf {
+        PathBuf::from(&self.path).join(".onix").join("vault_temp.json")
+    }
+
     pub fn onix_dir(&self) -> PathBuf {
         PathBuf::from(&self.path).join(".onix")
     }
@@ -0,0 +1,239 @@
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tokio::fs as async_fs;
+
+use crate::crypto::VaultKey;
+use crate::errors::AppError;
+
+/// Metadata for a single note file, as returned to the frontend.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FileInfo {
+    pub size: u64,
+    pub modified: i64,
+    pub is_dir: bool,
+}
+
+/// Reads and writes note files within a vault, transparently encrypting bodies at
+/// rest with the vault's key when one is held.
+#[derive(Debug, Default)]
+pub struct FileService;
+
+impl FileService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Resolve `relative_path` against `vault_root`, rejecting anything that would
+    /// escape the vault root (e.g. `../../etc/passwd`).
+    fn resolve(vault_root: &Path, relative_path: &str) -> Result<PathBuf, AppError> {
+        let root = vault_root
+            .canonicalize()
+            .map_err(|_| AppError::InvalidPath(format!("{} does not exist", vault_root.display())))?;
+
+        let candidate = vault_root.join(relative_path);
+        let parent = candidate.parent().unwrap_or(&candidate);
+        let canonical_parent = parent
+            .canonicalize()
+            .map_err(|_| AppError::InvalidPath(format!("{} does not exist", parent.display())))?;
+
+        if !canonical_parent.starts_with(&root) {
+            return Err(AppError::InvalidPath(format!(
+                "{} is outside the vault",
+                relative_path
+            )));
+        }
+
+        Ok(candidate)
+    }
+
+    /// Read a note's content, decrypting it with `key` when the vault is encrypted.
+    /// Callers must supply `Some(key)` for an encrypted vault - the caller (see
+    /// `commands::files::read_note`) gets that from `VaultService::vault_key`,
+    /// which itself errors while the vault is locked, so a locked vault's notes
+    /// can never be read.
+    pub async fn read_note(
+        &self,
+        vault_root: &Path,
+        key: Option<&VaultKey>,
+        relative_path: &str,
+    ) -> Result<String, AppError> {
+        let path = Self::resolve(vault_root, relative_path)?;
+        let raw = async_fs::read_to_string(&path).await?;
+
+        match key {
+            Some(key) => {
+                let decrypted = key.decrypt(raw.trim_end())?;
+                String::from_utf8(decrypted).map_err(|_| {
+                    AppError::InvalidMarkdown("Note content is not valid UTF-8".to_string())
+                })
+            }
+            None => Ok(raw),
+        }
+    }
+
+    /// Write a note's content, encrypting it with `key` when the vault is
+    /// encrypted. See [`FileService::read_note`] for how locking is enforced.
+    pub async fn write_note(
+        &self,
+        vault_root: &Path,
+        key: Option<&VaultKey>,
+        relative_path: &str,
+        content: &str,
+    ) -> Result<(), AppError> {
+        let path = Self::resolve(vault_root, relative_path)?;
+
+        if let Some(parent) = path.parent() {
+            async_fs::create_dir_all(parent).await?;
+        }
+
+        let on_disk = match key {
+            Some(key) => key.encrypt(content.as_bytes())?,
+            None => content.to_string(),
+        };
+
+        async_fs::write(&path, on_disk).await?;
+        Ok(())
+    }
+
+    /// Create a new, empty note. Fails if one already exists at `relative_path`.
+    pub async fn create_note(
+        &self,
+        vault_root: &Path,
+        key: Option<&VaultKey>,
+        relative_path: &str,
+    ) -> Result<(), AppError> {
+        let path = Self::resolve(vault_root, relative_path)?;
+        if path.exists() {
+            return Err(AppError::InvalidPath(format!(
+                "{} already exists",
+                relative_path
+            )));
+        }
+
+        self.write_note(vault_root, key, relative_path, "").await
+    }
+
+    pub async fn delete_note(&self, vault_root: &Path, relative_path: &str) -> Result<(), AppError> {
+        let path = Self::resolve(vault_root, relative_path)?;
+        async_fs::remove_file(&path).await?;
+        Ok(())
+    }
+
+    /// List every file under `vault_root`, relative to it, skipping hidden files
+    /// and anything inside a hidden directory (e.g. `.onix`). Note content
+    /// isn't touched, so this doesn't require the vault to be unlocked.
+    pub async fn list_notes(&self, vault_root: &Path) -> Result<Vec<String>, AppError> {
+        let root = vault_root.to_path_buf();
+
+        tokio::task::spawn_blocking(move || {
+            let mut notes = Vec::new();
+            Self::collect_notes(&root, &root, &mut notes)?;
+            Ok(notes)
+        })
+        .await
+        .map_err(|e| AppError::Unknown(format!("Note listing task panicked: {}", e)))?
+    }
+
+    fn collect_notes(root: &Path, dir: &Path, notes: &mut Vec<String>) -> Result<(), AppError> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let is_hidden = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with('.'));
+            if is_hidden {
+                continue;
+            }
+
+            if path.is_dir() {
+                Self::collect_notes(root, &path, notes)?;
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                notes.push(relative.to_string_lossy().to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn file_exists(&self, vault_root: &Path, relative_path: &str) -> Result<bool, AppError> {
+        let path = Self::resolve(vault_root, relative_path)?;
+        Ok(path.exists())
+    }
+
+    pub async fn get_file_info(
+        &self,
+        vault_root: &Path,
+        relative_path: &str,
+    ) -> Result<FileInfo, AppError> {
+        let path = Self::resolve(vault_root, relative_path)?;
+        let metadata = async_fs::metadata(&path).await?;
+
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        Ok(FileInfo {
+            size: metadata.len(),
+            modified,
+            is_dir: metadata.is_dir(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("onix-file-service-test-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn write_then_read_round_trips_without_a_key() {
+        let root = scratch_dir("plaintext");
+        let service = FileService::new();
+
+        service.write_note(&root, None, "note.md", "hello").await.unwrap();
+        let content = service.read_note(&root, None, "note.md").await.unwrap();
+
+        assert_eq!(content, "hello");
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn write_then_read_round_trips_with_a_key() {
+        let root = scratch_dir("encrypted");
+        let service = FileService::new();
+        let (key, _) = VaultKey::generate("password").unwrap();
+
+        service
+            .write_note(&root, Some(&key), "note.md", "secret content")
+            .await
+            .unwrap();
+
+        let on_disk = std::fs::read_to_string(root.join("note.md")).unwrap();
+        assert_ne!(on_disk, "secret content");
+
+        let content = service.read_note(&root, Some(&key), "note.md").await.unwrap();
+        assert_eq!(content, "secret content");
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn resolve_rejects_paths_that_escape_the_vault_root() {
+        let root = scratch_dir("escape");
+        let service = FileService::new();
+
+        let result = service.read_note(&root, None, "../../etc/passwd").await;
+        assert!(result.is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,19 +1,39 @@
+use crate::crypto::VaultKey;
 use crate::errors::AppError;
-use crate::models::vault::{CreateVaultRequest, RecentVault, Vault, VaultInfo};
+use crate::migrations;
+use crate::models::vault::{
+    CreateVaultRequest, FileIndexEntry, RecentVault, ScanMode, Vault, VaultInfo, VaultSettings,
+};
 use serde_json;
 
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use jwalk::WalkDir as ParWalkDir;
+use rayon::iter::{ParallelBridge, ParallelIterator};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
 use tokio::fs as async_fs;
+use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
-use walkdir::WalkDir;
+
+/// Controls whether a vault scan recomputes everything or only what changed.
+#[derive(Debug, Clone, Copy)]
+struct UpdateOptions {
+    /// `true` for a full scan (new vault, or first open); `false` for a cheap
+    /// incremental update that only adjusts counts for files that are new,
+    /// removed, or whose mtime changed.
+    initial: bool,
+}
 
 #[derive(Debug)]
 pub struct VaultService {
     current_vault: Arc<Mutex<Option<Vault>>>,
     recent_vaults: Arc<Mutex<Vec<RecentVault>>>,
     app_data_dir: PathBuf,
+    /// Key for the current vault, if encrypted and unlocked. Never persisted.
+    vault_key: Arc<Mutex<Option<VaultKey>>>,
 }
 
 impl VaultService {
@@ -24,6 +44,7 @@ impl VaultService {
             current_vault: Arc::new(Mutex::new(None)),
             recent_vaults: Arc::new(Mutex::new(Vec::new())),
             app_data_dir,
+            vault_key: Arc::new(Mutex::new(None)),
         };
 
         // Load recent vaults on initialization
@@ -57,14 +78,19 @@ impl VaultService {
             vault.config.description = Some(description);
         }
 
+        // Derive and hold the vault key if encryption was requested.
+        if let Some(password) = request.password {
+            let (key, encryption_config) = VaultKey::generate(&password)?;
+            vault.config.encryption = Some(encryption_config);
+            self.set_vault_key(Some(key))?;
+        }
+
         // Create .onix directory
         let onix_dir = vault.onix_dir();
         async_fs::create_dir_all(&onix_dir).await?;
 
         // Save vault configuration
-        let config_path = vault.config_path();
-        let config_json = serde_json::to_string_pretty(&vault)?;
-        async_fs::write(config_path, config_json).await?;
+        self.save_vault_config(&vault).await?;
 
         // Calculate vault statistics
         vault = self.calculate_vault_stats(vault).await?;
@@ -94,15 +120,44 @@ impl VaultService {
         }
 
         let config_path = path.join(".onix").join("vault.json");
+        let temp_config_path = path.join(".onix").join("vault_temp.json");
+
+        let mut migrated = false;
 
         let mut vault = if config_path.exists() {
             // Load existing vault configuration
-            let config_content = async_fs::read_to_string(config_path).await?;
-            let mut vault: Vault = serde_json::from_str(&config_content)
-                .map_err(|e| AppError::InvalidMarkdown(format!("Invalid vault config: {}", e)))?;
+            let config_content = async_fs::read_to_string(&config_path).await?;
 
-            // Update last opened timestamp
+            match Self::parse_vault_config(&config_content) {
+                Some((mut vault, did_migrate)) => {
+                    vault.last_opened = chrono::Utc::now().timestamp().to_string();
+                    migrated = did_migrate;
+                    vault
+                }
+                None => {
+                    // vault.json is corrupt, likely from a crash mid-write. Recover from
+                    // the temp file left behind by the atomic write, if it's usable.
+                    match Self::read_vault_config(&temp_config_path).await {
+                        Some((mut vault, did_migrate)) => {
+                            vault.last_opened = chrono::Utc::now().timestamp().to_string();
+                            migrated = did_migrate;
+                            vault
+                        }
+                        None => {
+                            return Err(AppError::InvalidMarkdown(
+                                "Invalid vault config: failed to parse vault.json".to_string(),
+                            ));
+                        }
+                    }
+                }
+            }
+        } else if let Some((mut vault, did_migrate)) =
+            Self::read_vault_config(&temp_config_path).await
+        {
+            // vault.json is missing outright (e.g. a crash between the temp write and
+            // the rename), but the temp file survived - recover from it.
             vault.last_opened = chrono::Utc::now().timestamp().to_string();
+            migrated = did_migrate;
             vault
         } else {
             // Create new vault configuration for existing directory
@@ -118,14 +173,18 @@ impl VaultService {
             // Create .onix directory and save config
             let onix_dir = vault.onix_dir();
             async_fs::create_dir_all(&onix_dir).await?;
-
-            let config_path = vault.config_path();
-            let config_json = serde_json::to_string_pretty(&vault)?;
-            async_fs::write(config_path, config_json).await?;
+            self.save_vault_config(&vault).await?;
 
             vault
         };
 
+        if migrated {
+            vault.config.modified_at = chrono::Utc::now().timestamp().to_string();
+        }
+
+        // A vault always opens locked if it's encrypted.
+        self.set_vault_key(None)?;
+
         // Calculate current statistics
         vault = self.calculate_vault_stats(vault).await?;
 
@@ -175,15 +234,17 @@ impl VaultService {
             }
 
             let config_path = path.join(".onix").join("vault.json");
+            let temp_config_path = path.join(".onix").join("vault_temp.json");
 
-            if config_path.exists() {
-                if let Ok(config_content) = async_fs::read_to_string(config_path).await {
-                    if let Ok(vault) = serde_json::from_str::<Vault>(&config_content) {
-                        let mut vault_info: VaultInfo = vault.into();
-                        vault_info.is_current = current_vault_id.as_ref() == Some(&vault_info.id);
-                        vault_infos.push(vault_info);
-                    }
-                }
+            let vault = match Self::read_vault_config(&config_path).await {
+                Some((vault, _)) => Some(vault),
+                None => Self::read_vault_config(&temp_config_path).await.map(|(v, _)| v),
+            };
+
+            if let Some(vault) = vault {
+                let mut vault_info: VaultInfo = vault.into();
+                vault_info.is_current = current_vault_id.as_ref() == Some(&vault_info.id);
+                vault_infos.push(vault_info);
             }
         }
 
@@ -197,6 +258,97 @@ impl VaultService {
             .lock()
             .map_err(|_| AppError::Unknown("Failed to acquire vault lock".to_string()))?;
         *current = None;
+        drop(current);
+
+        self.set_vault_key(None)?;
+        Ok(())
+    }
+
+    /// Unlock the current vault: derive its key from `password`, reject it if it
+    /// doesn't match the stored key MAC, then decrypt `encrypted_metadata` into
+    /// `metadata` in memory. The held key is also what `vault_key()` hands to
+    /// `commands::files` to encrypt/decrypt note bodies - while locked, `vault_key()`
+    /// errors and note reads/writes fail rather than touching plaintext.
+    pub fn unlock_vault(&self, password: String) -> Result<(), AppError> {
+        let mut current = self
+            .current_vault
+            .lock()
+            .map_err(|_| AppError::Unknown("Failed to acquire vault lock".to_string()))?;
+
+        let vault = current
+            .as_mut()
+            .ok_or_else(|| AppError::Unknown("No vault is open".to_string()))?;
+
+        let encryption = vault
+            .config
+            .encryption
+            .as_ref()
+            .ok_or_else(|| AppError::Unknown("Vault is not encrypted".to_string()))?;
+
+        let key = VaultKey::unlock(&password, encryption)?;
+
+        if let Some(encrypted) = &vault.config.encrypted_metadata {
+            let decrypted = key.decrypt(encrypted)?;
+            vault.config.metadata = serde_json::from_slice(&decrypted)
+                .map_err(|e| AppError::Unknown(format!("Corrupt vault metadata: {}", e)))?;
+        }
+
+        drop(current);
+        self.set_vault_key(Some(key))
+    }
+
+    /// Discard the in-memory derived key and decrypted metadata, re-locking the
+    /// current vault.
+    pub fn lock_vault(&self) -> Result<(), AppError> {
+        let mut current = self
+            .current_vault
+            .lock()
+            .map_err(|_| AppError::Unknown("Failed to acquire vault lock".to_string()))?;
+
+        if let Some(vault) = current.as_mut() {
+            if vault.config.encryption.is_some() {
+                vault.config.metadata = HashMap::new();
+            }
+        }
+
+        drop(current);
+        self.set_vault_key(None)
+    }
+
+    /// Derived key for the current vault, for callers that need to encrypt or
+    /// decrypt vault content directly. `Ok(None)` means the vault is unencrypted;
+    /// an error means it's encrypted but locked.
+    pub fn vault_key(&self) -> Result<Option<VaultKey>, AppError> {
+        let current = self.get_current_vault()?;
+        let Some(vault) = current else {
+            return Ok(None);
+        };
+
+        if vault.config.encryption.is_none() {
+            return Ok(None);
+        }
+
+        self.held_vault_key()?
+            .map(Some)
+            .ok_or_else(|| AppError::Unknown("Vault is locked".to_string()))
+    }
+
+    /// The held key, if any, regardless of whether a vault is current yet - used
+    /// while a vault is still being created, before it's set as current.
+    fn held_vault_key(&self) -> Result<Option<VaultKey>, AppError> {
+        let vault_key = self
+            .vault_key
+            .lock()
+            .map_err(|_| AppError::Unknown("Failed to acquire vault key lock".to_string()))?;
+        Ok(vault_key.clone())
+    }
+
+    fn set_vault_key(&self, key: Option<VaultKey>) -> Result<(), AppError> {
+        let mut vault_key = self
+            .vault_key
+            .lock()
+            .map_err(|_| AppError::Unknown("Failed to acquire vault key lock".to_string()))?;
+        *vault_key = key;
         Ok(())
     }
 
@@ -212,52 +364,325 @@ impl VaultService {
         Ok(config_path.exists())
     }
 
-    /// Calculate vault statistics (note count, total size)
-    async fn calculate_vault_stats(&self, mut vault: Vault) -> Result<Vault, AppError> {
-        let path = Path::new(&vault.path);
-        let mut note_count = 0u32;
-        let mut total_size = 0u64;
+    /// Calculate vault statistics (note count, total size) via a full scan.
+    async fn calculate_vault_stats(&self, vault: Vault) -> Result<Vault, AppError> {
+        self.update_vault_stats(vault, UpdateOptions { initial: true })
+            .await
+    }
 
-        for entry in WalkDir::new(path)
-            .follow_links(false)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let entry_path = entry.path();
+    /// Re-scan the current vault's notes incrementally, against the last index.
+    pub async fn refresh_vault(&self) -> Result<Vault, AppError> {
+        let vault = self
+            .get_current_vault()?
+            .ok_or_else(|| AppError::Unknown("No vault is open".to_string()))?;
 
-            // Skip hidden files and .onix directory
-            if self.should_exclude_path(entry_path, &vault.config.settings.exclude_patterns) {
-                continue;
+        let vault = self
+            .update_vault_stats(vault, UpdateOptions { initial: false })
+            .await?;
+
+        self.save_vault_config(&vault).await?;
+        self.set_current_vault(vault.clone())?;
+
+        Ok(vault)
+    }
+
+    /// Recompute vault statistics, either as a full scan or a cheap incremental
+    /// update, honoring the vault's configured `tree_mode`.
+    async fn update_vault_stats(
+        &self,
+        mut vault: Vault,
+        options: UpdateOptions,
+    ) -> Result<Vault, AppError> {
+        let vault_root = PathBuf::from(&vault.path);
+        let scan_root = match vault.config.settings.tree_mode {
+            ScanMode::Flat => vault_root.join(&vault.config.settings.default_note_location),
+            ScanMode::DepthFirst => vault_root.clone(),
+        };
+        let max_depth = match vault.config.settings.tree_mode {
+            ScanMode::Flat => Some(1),
+            ScanMode::DepthFirst => None,
+        };
+
+        let matcher = Self::build_exclude_matcher(&vault_root, &vault.config.settings)?;
+        let file_extensions = vault.config.settings.file_extensions.clone();
+        let worker_count = vault.config.settings.scan_workers.max(1);
+
+        if options.initial {
+            let (note_count, total_size, index) = Self::full_scan(
+                scan_root,
+                matcher,
+                file_extensions,
+                worker_count,
+                max_depth,
+                vault_root,
+            )
+            .await?;
+
+            vault.note_count = note_count;
+            vault.total_size = total_size;
+            self.save_vault_index(&vault, &index).await?;
+        } else {
+            let existing_index = self.load_vault_index(&vault).await.unwrap_or_default();
+
+            let (note_delta, size_delta, index) = Self::incremental_scan(
+                scan_root,
+                matcher,
+                file_extensions,
+                worker_count,
+                max_depth,
+                vault_root,
+                existing_index,
+            )
+            .await?;
+
+            vault.note_count = (vault.note_count as i64 + note_delta).max(0) as u32;
+            vault.total_size = (vault.total_size as i64 + size_delta).max(0) as u64;
+            self.save_vault_index(&vault, &index).await?;
+        }
+
+        Ok(vault)
+    }
+
+    /// Compile a vault's `exclude_patterns` (and its `.gitignore`, if
+    /// `respect_gitignore` is set) into a single matcher, built once per scan.
+    fn build_exclude_matcher(vault_root: &Path, settings: &VaultSettings) -> Result<Gitignore, AppError> {
+        let mut builder = GitignoreBuilder::new(vault_root);
+
+        for pattern in &settings.exclude_patterns {
+            builder.add_line(None, pattern).map_err(|e| {
+                AppError::Unknown(format!("Invalid exclude pattern '{}': {}", pattern, e))
+            })?;
+        }
+
+        if settings.respect_gitignore {
+            let gitignore_path = vault_root.join(".gitignore");
+            if gitignore_path.exists() {
+                if let Some(err) = builder.add(gitignore_path) {
+                    return Err(AppError::Unknown(format!("Invalid .gitignore: {}", err)));
+                }
             }
+        }
 
-            if entry_path.is_file() {
-                if let Some(extension) = entry_path.extension().and_then(|ext| ext.to_str()) {
-                    if vault
-                        .config
-                        .settings
-                        .file_extensions
-                        .contains(&extension.to_lowercase())
-                    {
-                        note_count += 1;
-                        if let Ok(metadata) = entry.metadata() {
-                            total_size += metadata.len();
-                        }
+        builder
+            .build()
+            .map_err(|e| AppError::Unknown(format!("Failed to build exclude matcher: {}", e)))
+    }
+
+    /// Full parallel scan of `scan_root`, rebuilding note count, size, and index.
+    async fn full_scan(
+        scan_root: PathBuf,
+        matcher: Gitignore,
+        file_extensions: Vec<String>,
+        worker_count: usize,
+        max_depth: Option<usize>,
+        vault_root: PathBuf,
+    ) -> Result<(u32, u64, HashMap<String, FileIndexEntry>), AppError> {
+        tokio::task::spawn_blocking(move || {
+            let mut walker = ParWalkDir::new(&scan_root)
+                .parallelism(jwalk::Parallelism::RayonNewPool(worker_count))
+                .follow_links(false);
+
+            if let Some(depth) = max_depth {
+                walker = walker.max_depth(depth);
+            }
+
+            let entries: Vec<(String, FileIndexEntry)> = walker
+                .into_iter()
+                .par_bridge()
+                .filter_map(|e| e.ok())
+                .filter_map(|entry| {
+                    Self::index_entry_for(entry.path(), &matcher, &file_extensions, &vault_root)
+                })
+                .collect();
+
+            let note_count = entries.len() as u32;
+            let total_size: u64 = entries.iter().map(|(_, e)| e.size).sum();
+            let index = entries.into_iter().collect::<HashMap<_, _>>();
+
+            (note_count, total_size, index)
+        })
+        .await
+        .map_err(|e| AppError::Unknown(format!("Vault scan task panicked: {}", e)))
+    }
+
+    /// Incremental scan: only adjusts counts for files that are new, removed, or
+    /// whose mtime differs from `existing_index`.
+    async fn incremental_scan(
+        scan_root: PathBuf,
+        matcher: Gitignore,
+        file_extensions: Vec<String>,
+        worker_count: usize,
+        max_depth: Option<usize>,
+        vault_root: PathBuf,
+        existing_index: HashMap<String, FileIndexEntry>,
+    ) -> Result<(i64, i64, HashMap<String, FileIndexEntry>), AppError> {
+        tokio::task::spawn_blocking(move || {
+            let mut walker = ParWalkDir::new(&scan_root)
+                .parallelism(jwalk::Parallelism::RayonNewPool(worker_count))
+                .follow_links(false);
+            if let Some(depth) = max_depth {
+                walker = walker.max_depth(depth);
+            }
+
+            let mut new_index = HashMap::with_capacity(existing_index.len());
+            let mut note_delta: i64 = 0;
+            let mut size_delta: i64 = 0;
+
+            for entry in walker.into_iter().filter_map(|e| e.ok()) {
+                let Some((relative, current)) =
+                    Self::index_entry_for(entry.path(), &matcher, &file_extensions, &vault_root)
+                else {
+                    continue;
+                };
+
+                match existing_index.get(&relative) {
+                    Some(previous) if previous.modified == current.modified => {
+                        // Unchanged - carry the prior entry forward untouched.
+                        new_index.insert(relative, previous.clone());
+                    }
+                    Some(previous) => {
+                        size_delta += current.size as i64 - previous.size as i64;
+                        new_index.insert(relative, current);
+                    }
+                    None => {
+                        note_delta += 1;
+                        size_delta += current.size as i64;
+                        new_index.insert(relative, current);
                     }
                 }
             }
+
+            // Anything left over in the old index wasn't seen this pass, so it's gone.
+            for (path, previous) in &existing_index {
+                if !new_index.contains_key(path) {
+                    note_delta -= 1;
+                    size_delta -= previous.size as i64;
+                }
+            }
+
+            (note_delta, size_delta, new_index)
+        })
+        .await
+        .map_err(|e| AppError::Unknown(format!("Vault scan task panicked: {}", e)))
+    }
+
+    /// Build an index entry for `path` if it's a non-excluded note file.
+    fn index_entry_for(
+        path: PathBuf,
+        matcher: &Gitignore,
+        file_extensions: &[String],
+        vault_root: &Path,
+    ) -> Option<(String, FileIndexEntry)> {
+        if Self::should_exclude_path(&path, vault_root, matcher) {
+            return None;
         }
 
-        vault.note_count = note_count;
-        vault.total_size = total_size;
+        if !path.is_file() {
+            return None;
+        }
 
-        Ok(vault)
+        let extension = path.extension().and_then(|ext| ext.to_str())?;
+        if !file_extensions.contains(&extension.to_lowercase()) {
+            return None;
+        }
+
+        let metadata = fs::metadata(&path).ok()?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        let relative = path
+            .strip_prefix(vault_root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+
+        Some((relative, FileIndexEntry { size: metadata.len(), modified }))
+    }
+
+    fn vault_index_path(vault: &Vault) -> PathBuf {
+        vault.onix_dir().join("index.json")
+    }
+
+    /// Persist the per-file index used by incremental scans.
+    async fn save_vault_index(
+        &self,
+        vault: &Vault,
+        index: &HashMap<String, FileIndexEntry>,
+    ) -> Result<(), AppError> {
+        let index_path = Self::vault_index_path(vault);
+        let temp_path = vault.onix_dir().join("index_temp.json");
+        let index_json = serde_json::to_string_pretty(index)?;
+        Self::write_atomic(&index_path, &temp_path, index_json.as_bytes()).await
     }
 
-    /// Save vault configuration to disk
+    /// Load the per-file index, or `None` if it's missing or unreadable.
+    async fn load_vault_index(&self, vault: &Vault) -> Option<HashMap<String, FileIndexEntry>> {
+        let index_path = Self::vault_index_path(vault);
+        if !index_path.exists() {
+            return None;
+        }
+
+        let content = async_fs::read_to_string(index_path).await.ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Save vault configuration to disk. For an encrypted vault, `metadata` is
+    /// encrypted into `encrypted_metadata` before writing and never hits disk in
+    /// plaintext; if the vault is currently locked, the existing on-disk
+    /// `encrypted_metadata` is carried forward untouched instead.
     async fn save_vault_config(&self, vault: &Vault) -> Result<(), AppError> {
-        let config_path = vault.config_path();
-        let config_json = serde_json::to_string_pretty(vault)?;
-        async_fs::write(config_path, config_json).await?;
+        let mut to_disk = vault.clone();
+
+        if to_disk.config.encryption.is_some() {
+            if let Some(key) = self.held_vault_key()? {
+                let metadata_json = serde_json::to_vec(&to_disk.config.metadata)?;
+                to_disk.config.encrypted_metadata = Some(key.encrypt(&metadata_json)?);
+            }
+            to_disk.config.metadata = HashMap::new();
+        }
+
+        let config_json = serde_json::to_string_pretty(&to_disk)?;
+        Self::write_atomic(&vault.config_path(), &vault.temp_config_path(), config_json.as_bytes())
+            .await
+    }
+
+    /// Read and parse a vault config file, returning `None` on any failure so
+    /// callers can fall back to another source.
+    async fn read_vault_config(path: &Path) -> Option<(Vault, bool)> {
+        if !path.exists() {
+            return None;
+        }
+
+        let content = async_fs::read_to_string(path).await.ok()?;
+        Self::parse_vault_config(&content)
+    }
+
+    /// Parse a vault config's raw JSON, migrating it to the current schema version
+    /// first. Returns the parsed vault and whether a migration ran.
+    fn parse_vault_config(content: &str) -> Option<(Vault, bool)> {
+        let raw_config: serde_json::Value = serde_json::from_str(content).ok()?;
+        let (migrated_config, did_migrate) = migrations::migrate_to_current(raw_config);
+        let vault: Vault = serde_json::from_value(migrated_config).ok()?;
+        Some((vault, did_migrate))
+    }
+
+    /// Write `contents` to `path` via write-to-temp-then-rename, so a crash or power
+    /// loss mid-write can never leave `path` truncated or unparseable. `sync_all`
+    /// forces the temp file to the physical device before the rename - without it,
+    /// content can still be sitting in the page cache when a crash hits. `rename` is
+    /// atomic as long as `temp_path` and `path` are on the same filesystem.
+    async fn write_atomic(path: &Path, temp_path: &Path, contents: &[u8]) -> Result<(), AppError> {
+        let mut file = async_fs::File::create(temp_path).await?;
+        file.write_all(contents).await?;
+        file.flush().await?;
+        file.sync_all().await?;
+        drop(file);
+
+        async_fs::rename(temp_path, path).await?;
         Ok(())
     }
 
@@ -320,8 +745,11 @@ impl VaultService {
         fs::create_dir_all(&self.app_data_dir)?;
 
         let recent_vaults_path = self.app_data_dir.join("recent_vaults.json");
+        let temp_path = self.app_data_dir.join("recent_vaults_temp.json");
         let content = serde_json::to_string_pretty(recent_vaults)?;
-        fs::write(recent_vaults_path, content)?;
+
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, &recent_vaults_path)?;
 
         Ok(())
     }
@@ -335,24 +763,33 @@ impl VaultService {
         Ok(app_data.join("onix"))
     }
 
-    /// Check if path should be excluded based on patterns
-    fn should_exclude_path(&self, path: &Path, exclude_patterns: &[String]) -> bool {
-        let path_str = path.to_string_lossy();
-
-        for pattern in exclude_patterns {
-            if path_str.contains(pattern) {
-                return true;
-            }
+    /// Check a path against the compiled exclude matcher, gitignore-style: `*`,
+    /// `**`, directory anchors (`/node_modules/`), and `!`-negation are all
+    /// resolved by `ignore::gitignore::Gitignore`, matched against `path` relative
+    /// to the vault root rather than the absolute string.
+    fn should_exclude_path(path: &Path, vault_root: &Path, matcher: &Gitignore) -> bool {
+        let relative = path.strip_prefix(vault_root).unwrap_or(path);
+        if relative.as_os_str().is_empty() {
+            return false;
         }
 
-        // Always exclude hidden files/directories
-        if let Some(file_name) = path.file_name().and_then(|name| name.to_str()) {
-            if file_name.starts_with('.') {
-                return true;
-            }
+        // Always exclude hidden files/directories, including a file nested under a
+        // hidden ancestor (e.g. `.git/objects/...`) even though its own name isn't
+        // dot-prefixed.
+        let has_hidden_component = relative
+            .components()
+            .any(|c| c.as_os_str().to_str().is_some_and(|s| s.starts_with('.')));
+        if has_hidden_component {
+            return true;
         }
 
-        false
+        // `matched` only checks `relative` itself; a pattern like `node_modules`
+        // would otherwise miss every file underneath it. `matched_path_or_any_parents`
+        // also checks ancestor directories, so a match on a parent excludes everything
+        // nested inside it too.
+        matcher
+            .matched_path_or_any_parents(relative, path.is_dir())
+            .is_ignore()
     }
 }
 
@@ -361,3 +798,49 @@ impl Default for VaultService {
         Self::new().expect("Failed to initialize VaultService")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(patterns: &[&str]) -> Gitignore {
+        let root = PathBuf::from("/vault");
+        let mut builder = GitignoreBuilder::new(&root);
+        for pattern in patterns {
+            builder.add_line(None, pattern).unwrap();
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn excludes_files_matched_directly() {
+        let root = PathBuf::from("/vault");
+        let m = matcher(&["*.tmp"]);
+        assert!(VaultService::should_exclude_path(&root.join("scratch.tmp"), &root, &m));
+        assert!(!VaultService::should_exclude_path(&root.join("note.md"), &root, &m));
+    }
+
+    #[test]
+    fn excludes_files_nested_under_an_excluded_directory() {
+        let root = PathBuf::from("/vault");
+        let m = matcher(&["node_modules"]);
+        let nested = root.join("node_modules").join("pkg").join("index.js");
+        assert!(VaultService::should_exclude_path(&nested, &root, &m));
+    }
+
+    #[test]
+    fn excludes_files_nested_under_a_hidden_directory() {
+        let root = PathBuf::from("/vault");
+        let m = matcher(&[]);
+        let nested = root.join(".obsidian").join("templates").join("note.md");
+        assert!(VaultService::should_exclude_path(&nested, &root, &m));
+    }
+
+    #[test]
+    fn does_not_exclude_ordinary_nested_notes() {
+        let root = PathBuf::from("/vault");
+        let m = matcher(&["node_modules"]);
+        let nested = root.join("projects").join("note.md");
+        assert!(!VaultService::should_exclude_path(&nested, &root, &m));
+    }
+}